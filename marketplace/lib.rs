@@ -1,19 +1,260 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[ink::contract]
+/// Chain extension for querying richer settlement data (e.g. a seller's
+/// balance of a runtime-native asset) from the underlying Substrate runtime,
+/// for cases the contract's own `Balance`/`transferred_value` can't answer.
+#[ink::chain_extension]
+pub trait MarketplaceChainExtension {
+    type ErrorCode = MarketplaceExtensionError;
+
+    /// Queries `account`'s balance of the runtime's settlement asset
+    /// (e.g. via `pallet-assets`) through a custom runtime call.
+    #[ink(extension = 1)]
+    fn fetch_asset_balance(account: ink::primitives::AccountId) -> Balance;
+}
+
+/// Status codes `MarketplaceChainExtension` calls can fail with.
+#[derive(scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(Debug, scale_info::TypeInfo))]
+pub enum MarketplaceExtensionError {
+    BalanceQueryFailed,
+}
+
+impl ink::env::chain_extension::FromStatusCode for MarketplaceExtensionError {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            _ => Err(Self::BalanceQueryFailed),
+        }
+    }
+}
+
+/// Custom environment wiring `MarketplaceChainExtension` into the contract,
+/// otherwise identical to `ink::env::DefaultEnvironment`.
+#[derive(Clone)]
+pub struct MarketplaceEnv;
+
+impl ink::env::Environment for MarketplaceEnv {
+    const MAX_EVENT_TOPICS: usize =
+        <ink::env::DefaultEnvironment as ink::env::Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <ink::env::DefaultEnvironment as ink::env::Environment>::AccountId;
+    type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
+    type Hash = <ink::env::DefaultEnvironment as ink::env::Environment>::Hash;
+    type BlockNumber = <ink::env::DefaultEnvironment as ink::env::Environment>::BlockNumber;
+    type Timestamp = <ink::env::DefaultEnvironment as ink::env::Environment>::Timestamp;
+
+    type ChainExtension = MarketplaceChainExtension;
+}
+
+#[ink::contract(env = crate::MarketplaceEnv)]
 mod marketplace {
 
-    use risc0_zkvm::sha::Digest;
+    use crate::MarketplaceExtensionError;
+
+    use risc0_zkvm::{
+        sha::{Impl, Sha256},
+        Receipt,
+    };
 
     use ink::{
-        prelude::{collections::BTreeSet, string::String, string::ToString, vec::Vec},
+        prelude::{string::String, vec::Vec},
         storage::Mapping,
         LangError,
     };
 
-    #[ink(storage)]
+    /// Image ID of the `reputation` RISC Zero guest
+    /// (`risc0/methods/guest/src/bin/reputation.rs`), re-exported from the
+    /// `methods` crate's build-time bindings rather than duplicated here, so
+    /// this constant can never drift from the guest it's meant to verify.
+    use methods::REPUTATION_ID;
+
+    /// Depth of the seller-commitment Merkle tree. Bounds `register_seller` to
+    /// O(`MERKLE_DEPTH`) and caps the anonymity set at `2^MERKLE_DEPTH` sellers.
+    const MERKLE_DEPTH: u32 = 20;
+
+    /// How many historical roots `verify_reputation_proof` accepts, so a
+    /// prover doesn't race a just-landed `register_seller` for their proof.
+    const RECENT_ROOTS_CAPACITY: u32 = 32;
+
+    /// A bid landing within this many blocks of an auction's `end_block`
+    /// moves `end_block` to this many blocks after the bid, to deter snipe bids.
+    const BID_EXTENSION_WINDOW: BlockNumber = 10;
+
+    /// Image ID of the `aggregation` RISC Zero guest
+    /// (`risc0/methods/guest/src/bin/aggregation.rs`), taken from the
+    /// `methods` crate's build-time bindings like `REPUTATION_ID`.
+    use methods::AGGREGATION_ID;
+
+    /// Errors from the anonymous seller-reputation gate.
+    #[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ReputationError {
+        /// The committed `nullifier_hash` has already been spent.
+        NullifierSpent,
+        /// The receipt's seal did not verify against `REPUTATION_ID`.
+        InvalidProof,
+        /// The journal's `reputation_threshold` is below what the sale requires.
+        ThresholdNotMet,
+        /// The journal did not have the expected `(root, nullifier, threshold)` layout.
+        MalformedJournal,
+        /// The journal's `commitment_root` isn't the current root or one of the
+        /// last `RECENT_ROOTS_CAPACITY` roots the contract has seen.
+        UnknownCommitmentRoot,
+        /// The seller-commitment Merkle tree is full; `next_index` has reached
+        /// `2^MERKLE_DEPTH` and no further leaves can be registered.
+        MerkleTreeFull,
+        /// Caller isn't the asset's real owner, so they can't list it for
+        /// sale or claim it as theirs.
+        NotAssetOwner,
+        /// `kind` isn't a listable starting state: a `FixedPrice` of `0`, or
+        /// an `EnglishAuction` with a non-zero `highest_bid`/`highest_bidder`
+        /// already set, a zero `reserve`, or an `end_block` that isn't in the
+        /// future.
+        InvalidSaleKind,
+    }
+
+    /// Decoded contents of the `reputation` guest's journal: `commitment_root`,
+    /// `nullifier_hash`, then a little-endian `reputation_threshold`.
+    struct ReputationJournal {
+        commitment_root: Hash,
+        nullifier_hash: Hash,
+        reputation_threshold: u32,
+    }
+
+    impl ReputationJournal {
+        fn decode(journal: &[u8]) -> Result<Self, ReputationError> {
+            if journal.len() != 68 {
+                return Err(ReputationError::MalformedJournal);
+            }
+            let mut root_bytes = [0u8; 32];
+            root_bytes.copy_from_slice(&journal[0..32]);
+            let mut nullifier_bytes = [0u8; 32];
+            nullifier_bytes.copy_from_slice(&journal[32..64]);
+            let mut threshold_bytes = [0u8; 4];
+            threshold_bytes.copy_from_slice(&journal[64..68]);
+            Ok(Self {
+                commitment_root: Hash::from(root_bytes),
+                nullifier_hash: Hash::from(nullifier_bytes),
+                reputation_threshold: u32::from_le_bytes(threshold_bytes),
+            })
+        }
+    }
+
+    /// The journal and seal produced by the `reputation` RISC Zero guest, as
+    /// handed to `put_asset_on_sale`/`update_seller_reputation`.
+    #[derive(scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(Debug, scale_info::TypeInfo))]
+    pub struct ReputationProof {
+        journal: Vec<u8>,
+        seal: Vec<u8>,
+    }
+
+    /// The journal and seal produced by the `aggregation` RISC Zero guest, as
+    /// handed to `rollup_commit`.
+    #[derive(scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(Debug, scale_info::TypeInfo))]
+    pub struct AggregationProof {
+        journal: Vec<u8>,
+        seal: Vec<u8>,
+    }
+
+    /// Errors from committing an off-chain aggregated-review rollup.
+    #[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum RollupError {
+        /// The journal did not have the expected
+        /// `(seller, reputation_delta, queue_version, reviews_hash)` layout.
+        MalformedJournal,
+        /// The journal's committed `seller` doesn't match the `seller`
+        /// argument, or its committed `queue_version` doesn't match
+        /// `expected_queue_version`.
+        SellerMismatch,
+        /// `expected_queue_version` doesn't match the seller's current queue
+        /// version — the queue changed since the worker read it.
+        VersionMismatch,
+        /// The journal's committed `reviews_hash` doesn't match a hash of the
+        /// seller's current queued reviews — the attestation wasn't computed
+        /// over exactly the queue it's settling.
+        ReviewsMismatch,
+        /// The receipt's seal did not verify against `AGGREGATION_ID`.
+        InvalidProof,
+    }
+
+    /// Decoded contents of the `aggregation` guest's journal: `seller`, then a
+    /// little-endian `reputation_delta`, then a little-endian `queue_version`,
+    /// then `reviews_hash`.
+    struct AggregationJournal {
+        seller: AccountId,
+        /// This batch's contribution to the seller's reputation, to be added
+        /// to (not replace) their existing total — the guest only ever sees
+        /// the queued reviews, never the seller's running score.
+        reputation_delta: u32,
+        queue_version: u32,
+        reviews_hash: Hash,
+    }
+
+    impl AggregationJournal {
+        fn decode(journal: &[u8]) -> Result<Self, RollupError> {
+            if journal.len() != 72 {
+                return Err(RollupError::MalformedJournal);
+            }
+            let mut seller_bytes = [0u8; 32];
+            seller_bytes.copy_from_slice(&journal[0..32]);
+            let mut reputation_delta_bytes = [0u8; 4];
+            reputation_delta_bytes.copy_from_slice(&journal[32..36]);
+            let mut queue_version_bytes = [0u8; 4];
+            queue_version_bytes.copy_from_slice(&journal[36..40]);
+            let mut reviews_hash_bytes = [0u8; 32];
+            reviews_hash_bytes.copy_from_slice(&journal[40..72]);
+            Ok(Self {
+                seller: AccountId::from(seller_bytes),
+                reputation_delta: u32::from_le_bytes(reputation_delta_bytes),
+                queue_version: u32::from_le_bytes(queue_version_bytes),
+                reviews_hash: Hash::from(reviews_hash_bytes),
+            })
+        }
+    }
+
+    /// A privileged role grantable to an account. `owner` is always
+    /// implicitly an `Admin` without needing an entry in `roles`.
+    #[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Role {
+        Admin,
+        Moderator,
+    }
+
+    impl Role {
+        /// Whether this role satisfies a `minimum` requirement. `Admin`
+        /// satisfies everything; `Moderator` only satisfies itself.
+        fn satisfies(&self, minimum: Role) -> bool {
+            matches!((self, minimum), (Role::Admin, _) | (Role::Moderator, Role::Moderator))
+        }
+    }
+
+    /// Errors from the owner/role access-control gate.
+    #[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AccessError {
+        /// Caller is not `owner`.
+        NotOwner,
+        /// Caller holds no role, or a role below what the message requires.
+        Unauthorized,
+        /// `set_code_hash` failed.
+        UpgradeFailed,
+    }
 
+    #[ink(storage)]
     pub struct Marketplace {
+        /// Account that can manage roles and `upgrade` the contract.
+        owner: AccountId,
+        /// Privileged roles granted to accounts other than `owner`, who is
+        /// always implicitly an `Admin`.
+        roles: Mapping<AccountId, Role>,
         /// List of all users.
         users: Vec<UserProfile>,
         /// List of all assets.
@@ -21,7 +262,59 @@ mod marketplace {
         current_sale: Sale,
         /// Mapping between Hash and bool
         spent_nullifier: Mapping<Hash, bool>,
-        commitments: BTreeSet<Hash>,
+        /// Current root of the seller-commitment Merkle tree.
+        commitment_root: Hash,
+        /// Index the next `register_seller` leaf will be inserted at.
+        next_index: u32,
+        /// Right-edge filled subtree node at each level, keyed by level.
+        filled_subtrees: Mapping<u32, Hash>,
+        /// Ring buffer of the last `RECENT_ROOTS_CAPACITY` roots, keyed by
+        /// `index % RECENT_ROOTS_CAPACITY`.
+        recent_roots: Mapping<u32, Hash>,
+        /// Next slot `recent_roots` will be written to.
+        recent_roots_cursor: u32,
+        /// Escrowed purchase funds, keyed by `asset_id`.
+        escrows: Mapping<u32, EscrowEntry>,
+        /// Encrypted reviews queued for each seller, awaiting aggregation by
+        /// an off-chain worker.
+        review_queue: Mapping<AccountId, Vec<Vec<u8>>>,
+        /// Nonce bumped every time a review is queued for a seller, echoed
+        /// back by `rollup_commit` to detect a queue that changed since the
+        /// worker read it.
+        queue_version: Mapping<AccountId, u32>,
+    }
+
+    /// Lifecycle of `current_sale`. Fixed-price sales move
+    /// `Listed` -> `Closed`; auctions move `AuctionOpen` -> `AuctionSettled`.
+    #[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum SaleStatus {
+        Closed,
+        Listed,
+        AuctionOpen,
+        AuctionSettled,
+    }
+
+    /// What a `Sale` is selling: a flat fixed price, or an English auction
+    /// tracking the current best bid.
+    #[derive(scale::Decode, scale::Encode, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum SaleKind {
+        FixedPrice {
+            price: Balance,
+        },
+        EnglishAuction {
+            reserve: Balance,
+            highest_bid: Balance,
+            highest_bidder: Option<AccountId>,
+            end_block: BlockNumber,
+        },
     }
 
     #[derive(scale::Decode, scale::Encode)]
@@ -30,8 +323,8 @@ mod marketplace {
         derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
     pub struct Sale {
-        status: String, //Write like an enum after
-        prize: u32,
+        status: SaleStatus,
+        kind: SaleKind,
         asset_id: u32,
         // seller_reputation - pending reputation to add in a way
     }
@@ -71,75 +364,649 @@ mod marketplace {
         seller_id: AccountId,
     }
 
+    /// Event emitted when an encrypted review is queued for a seller.
+    #[ink(event)]
+    pub struct ReviewQueued {
+        seller: AccountId,
+        queue_version: u32,
+    }
+
+    /// Event emitted when the off-chain worker's aggregated reviews are
+    /// committed and a seller's reputation is updated.
+    #[ink(event)]
+    pub struct RollupCommitted {
+        seller: AccountId,
+        new_reputation: u32,
+    }
+
+    /// Event emitted when `owner` changes.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        previous_owner: AccountId,
+        new_owner: AccountId,
+    }
+
+    /// Event emitted when the contract's code is upgraded.
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        code_hash: Hash,
+    }
+
+    /// Funds locked for a single asset purchase until the buyer releases them
+    /// to the seller, or the sale is cancelled and they're refunded.
+    #[derive(scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct EscrowEntry {
+        buyer: AccountId,
+        seller: AccountId,
+        amount: Balance,
+        released: bool,
+    }
+
+    /// Errors from the asset-purchase escrow.
+    #[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum EscrowError {
+        /// The asset isn't in the contract's inventory, has no escrow, or its
+        /// auction ended without a bid.
+        NoActiveSale,
+        /// The current sale isn't in a state that allows this transition.
+        InvalidSaleStatus,
+        /// `transferred_value` did not match the sale's price, reserve, or
+        /// current highest bid.
+        WrongAmount,
+        /// Caller is neither the escrow's buyer nor its seller.
+        Unauthorized,
+        /// The escrow for this asset has already been released or refunded.
+        AlreadySettled,
+        /// The native transfer out of the contract failed.
+        TransferFailed,
+    }
+
     impl Marketplace {
         /// Constructor that initializes the marketplace
         #[ink(constructor)]
         pub fn new(assets_list: Vec<Asset>, users_list: Vec<UserProfile>) -> Self {
             let init_sale = Sale {
-                status: "Closed".to_string(),
-                prize: 0,
+                status: SaleStatus::Closed,
+                kind: SaleKind::FixedPrice { price: 0 },
                 asset_id: 10,
             };
             let mk = Self {
+                owner: Self::env().caller(),
+                roles: Mapping::new(),
                 users: Vec::new(),
                 assets: assets_list,
                 current_sale: init_sale,
-                commitments: BTreeSet::new(),
                 spent_nullifier: Mapping::new(),
+                commitment_root: Self::zero_hash(MERKLE_DEPTH),
+                next_index: 0,
+                filled_subtrees: Mapping::new(),
+                recent_roots: Mapping::new(),
+                recent_roots_cursor: 0,
+                escrows: Mapping::new(),
+                review_queue: Mapping::new(),
+                queue_version: Mapping::new(),
             };
             mk
         }
 
+        /// Errors unless `caller` is `owner`.
+        fn ensure_owner(&self, caller: AccountId) -> Result<(), AccessError> {
+            if caller == self.owner {
+                Ok(())
+            } else {
+                Err(AccessError::NotOwner)
+            }
+        }
+
+        /// Errors unless `caller` is `owner` or holds a role satisfying
+        /// `minimum`.
+        fn ensure_role(&self, caller: AccountId, minimum: Role) -> Result<(), AccessError> {
+            if caller == self.owner {
+                return Ok(());
+            }
+            match self.roles.get(caller) {
+                Some(role) if role.satisfies(minimum) => Ok(()),
+                _ => Err(AccessError::Unauthorized),
+            }
+        }
+
+        /// Grants or revokes `account`'s role. Owner-only.
+        #[ink(message)]
+        pub fn set_role(&mut self, account: AccountId, role: Option<Role>) -> Result<(), AccessError> {
+            self.ensure_owner(self.env().caller())?;
+            match role {
+                Some(role) => self.roles.insert(account, &role),
+                None => self.roles.remove(account),
+            };
+            Ok(())
+        }
+
+        /// Transfers ownership to `new_owner`. Owner-only.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), AccessError> {
+            let caller = self.env().caller();
+            self.ensure_owner(caller)?;
+            self.owner = new_owner;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner: caller,
+                new_owner,
+            });
+            Ok(())
+        }
+
+        /// Migrates the contract to `code_hash` via `set_code_hash`, keeping
+        /// all existing storage (`users`, `assets`, the seller-commitment
+        /// tree, and `spent_nullifier` among it). Owner-only.
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), AccessError> {
+            self.ensure_owner(self.env().caller())?;
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| AccessError::UpgradeFailed)?;
+            self.env().emit_event(CodeUpgraded { code_hash });
+            Ok(())
+        }
+
+        /// Reduces `seller`'s reputation by `amount`. Requires at least the
+        /// `Moderator` role.
+        #[ink(message)]
+        pub fn slash_reputation(&mut self, seller: AccountId, amount: u32) -> Result<(), AccessError> {
+            self.ensure_role(self.env().caller(), Role::Moderator)?;
+            if let Some(user) = self.users.iter_mut().find(|user| user.account_id == seller) {
+                user.reputation = user.reputation.saturating_sub(amount);
+            }
+            Ok(())
+        }
+
+        /// Discards `seller`'s queued reviews without aggregating them,
+        /// bumping `queue_version` so any in-flight `rollup_commit` for the
+        /// discarded queue is rejected. Requires at least the `Admin` role.
+        #[ink(message)]
+        pub fn flush_review_queue(&mut self, seller: AccountId) -> Result<(), AccessError> {
+            self.ensure_role(self.env().caller(), Role::Admin)?;
+            self.review_queue.remove(seller);
+            let queue_version = self.queue_version.get(seller).unwrap_or(0) + 1;
+            self.queue_version.insert(seller, &queue_version);
+            Ok(())
+        }
+
+        /// Inserts `new_hash` as the next leaf of the seller-commitment Merkle
+        /// tree, updating the right-edge filled subtrees and the root in
+        /// O(`MERKLE_DEPTH`). Returns the new root.
         #[ink(message)]
         ///Register new seller
-        pub fn register_seller(&mut self, new_hash: Hash) {
-            self.commitments.insert(new_hash);
+        pub fn register_seller(&mut self, new_hash: Hash) -> Result<Hash, ReputationError> {
+            if self.next_index >= 1u32.checked_shl(MERKLE_DEPTH).unwrap_or(u32::MAX) {
+                return Err(ReputationError::MerkleTreeFull);
+            }
+
+            let mut index = self.next_index;
+            let mut current = new_hash;
+            for level in 0..MERKLE_DEPTH {
+                if index % 2 == 0 {
+                    self.filled_subtrees.insert(level, &current);
+                    current = Self::hash_pair(current, Self::zero_hash(level));
+                } else {
+                    let left = self
+                        .filled_subtrees
+                        .get(level)
+                        .unwrap_or_else(|| Self::zero_hash(level));
+                    current = Self::hash_pair(left, current);
+                }
+                index /= 2;
+            }
+            self.next_index += 1;
+            self.commitment_root = current;
+            self.push_recent_root(current);
+            Ok(current)
+        }
+
+        /// Current root of the seller-commitment Merkle tree, for off-chain
+        /// provers building a membership witness.
+        #[ink(message)]
+        pub fn commitment_root(&self) -> Hash {
+            self.commitment_root
+        }
+
+        /// Hash of an empty subtree of the given `level` (0 = an empty leaf).
+        fn zero_hash(level: u32) -> Hash {
+            let mut hash = Hash::from([0u8; 32]);
+            for _ in 0..level {
+                hash = Self::hash_pair(hash, hash);
+            }
+            hash
+        }
+
+        /// Combines two sibling nodes with the same SHA-256 the `reputation`
+        /// guest uses, so on-chain and in-guest tree hashing agree.
+        fn hash_pair(left: Hash, right: Hash) -> Hash {
+            let mut preimage = Vec::with_capacity(64);
+            preimage.extend_from_slice(left.as_ref());
+            preimage.extend_from_slice(right.as_ref());
+            Hash::from(*Impl::hash_bytes(&preimage).as_bytes())
+        }
+
+        /// Records `root` as the most recent entry in the `recent_roots` ring
+        /// buffer.
+        fn push_recent_root(&mut self, root: Hash) {
+            self.recent_roots
+                .insert(self.recent_roots_cursor, &root);
+            self.recent_roots_cursor = (self.recent_roots_cursor + 1) % RECENT_ROOTS_CAPACITY;
+        }
+
+        /// Whether `root` is the current commitment root or one of the last
+        /// `RECENT_ROOTS_CAPACITY` roots the contract has seen.
+        fn is_known_root(&self, root: Hash) -> bool {
+            if root == self.commitment_root {
+                return true;
+            }
+            (0..RECENT_ROOTS_CAPACITY).any(|i| self.recent_roots.get(i) == Some(root))
+        }
+
+        /// Verifies `proof` against `REPUTATION_ID`, checks that the committed
+        /// `reputation_threshold` meets `required_threshold`, and marks the
+        /// journal's `nullifier_hash` as spent so it cannot be reused.
+        ///
+        /// Returns the proof's committed `commitment_root` on success.
+        fn verify_reputation_proof(
+            &mut self,
+            proof: &ReputationProof,
+            required_threshold: u32,
+        ) -> Result<Hash, ReputationError> {
+            let journal = ReputationJournal::decode(&proof.journal)?;
+
+            if !self.is_known_root(journal.commitment_root) {
+                return Err(ReputationError::UnknownCommitmentRoot);
+            }
+
+            if journal.reputation_threshold < required_threshold {
+                return Err(ReputationError::ThresholdNotMet);
+            }
+
+            if self
+                .spent_nullifier
+                .get(journal.nullifier_hash)
+                .unwrap_or(false)
+            {
+                return Err(ReputationError::NullifierSpent);
+            }
+
+            let receipt = Receipt {
+                journal: proof.journal.clone(),
+                seal: proof.seal.clone(),
+            };
+            receipt
+                .verify(REPUTATION_ID)
+                .map_err(|_| ReputationError::InvalidProof)?;
+
+            self.spent_nullifier.insert(journal.nullifier_hash, &true);
+            Ok(journal.commitment_root)
         }
 
-        /// Modify Item on Sale
+        /// Lists `asset` as `current_sale`, either as a fixed-price sale or an
+        /// English auction depending on `kind`. Only the asset's real owner —
+        /// `self.env().caller()`, checked against `self.assets` for an
+        /// existing listing or the caller-supplied `asset` otherwise — may
+        /// list it, and `kind` must be a fresh starting state (a positive
+        /// price, or an auction with no bid yet and an `end_block` still in
+        /// the future), not one a caller could pre-seed as already won.
+        /// If `asset` is already marked `purchasable`, `current_sale` is left
+        /// unchanged and only the inventory entry is updated.
+        #[ink(message)]
         pub fn put_asset_on_sale(
-            mut self,
+            &mut self,
             mut asset: Asset,
-            zk_proof: Digest,
-            account: AccountId,
-        ) -> Result<u32, LangError> {
+            zk_proof: ReputationProof,
+            reputation_threshold: u32,
+            kind: SaleKind,
+        ) -> Result<u32, ReputationError> {
+            self.verify_reputation_proof(&zk_proof, reputation_threshold)?;
+
+            let caller = self.env().caller();
+            let real_owner = match self.asset_index(asset.id) {
+                Some(index) => self.assets[index].account_owner,
+                None => asset.account_owner,
+            };
+            if caller != real_owner {
+                return Err(ReputationError::NotAssetOwner);
+            }
+            asset.account_owner = caller;
+
+            match kind {
+                SaleKind::FixedPrice { price } if price > 0 => {}
+                SaleKind::EnglishAuction {
+                    reserve,
+                    highest_bid: 0,
+                    highest_bidder: None,
+                    end_block,
+                } if reserve > 0 && end_block > self.env().block_number() => {}
+                _ => return Err(ReputationError::InvalidSaleKind),
+            }
+
             if !asset.purchasable {
                 asset.purchasable = true;
-                let ongoing_sale = Sale {
-                    status: "OnGoing".to_string(),
-                    prize: 0,
+                let status = match kind {
+                    SaleKind::FixedPrice { .. } => SaleStatus::Listed,
+                    SaleKind::EnglishAuction { .. } => SaleStatus::AuctionOpen,
+                };
+                self.current_sale = Sale {
+                    status,
+                    kind,
                     asset_id: asset.id,
                 };
-                self.current_sale = ongoing_sale;
-                // Verify the proof of reputation
-                // Put nft in the contract, and set the price
-                // ybort abort if nullifier was spent
             }
-            self.env().emit_event(ItemOnsale { seller_id: account });
-            // TODO: Add Result output
-            unimplemented!()
+
+            // Persist the listing into `self.assets` so `buy_asset` and
+            // `settle_auction` read the seller back from the contract's own
+            // inventory instead of a caller-supplied `Asset` at settlement time.
+            let asset_id = asset.id;
+            match self.asset_index(asset_id) {
+                Some(index) => self.assets[index] = asset,
+                None => self.assets.push(asset),
+            }
+
+            self.env().emit_event(ItemOnsale { seller_id: caller });
+            Ok(asset_id)
         }
 
+        /// Queries `account`'s balance of the runtime's settlement asset
+        /// through `MarketplaceChainExtension`, for settlement that needs
+        /// more than the contract's own native `Balance`.
         #[ink(message)]
-        pub fn buy_asset(&mut self, asset: Asset, account: AccountId, price: u32) {
-            // check balance of account, compare to price
-            // transfer of the account Id to the asset
+        pub fn fetch_asset_balance(
+            &self,
+            account: AccountId,
+        ) -> Result<Balance, MarketplaceExtensionError> {
+            self.env().extension().fetch_asset_balance(account)
+        }
+
+        /// Index of the asset with `asset_id` in `self.assets`, if it exists.
+        fn asset_index(&self, asset_id: u32) -> Option<usize> {
+            self.assets.iter().position(|asset| asset.id == asset_id)
+        }
+
+        /// Buys the asset currently listed as a fixed-price `current_sale`,
+        /// locking the real transferred value (`self.env().transferred_value()`)
+        /// in escrow and reassigning ownership to the real caller
+        /// (`self.env().caller()`) rather than trusting caller-supplied
+        /// price/account arguments. The seller is read from the contract's own
+        /// `self.assets` inventory, never from a caller-supplied `Asset`.
+        /// The seller is paid out once the buyer calls `release_funds`.
+        #[ink(message, payable)]
+        pub fn buy_asset(&mut self, asset_id: u32) -> Result<(), EscrowError> {
+            let account = self.env().caller();
+
+            if self.current_sale.status != SaleStatus::Listed
+                || self.current_sale.asset_id != asset_id
+            {
+                return Err(EscrowError::InvalidSaleStatus);
+            }
+            let SaleKind::FixedPrice { price: listed_price } = self.current_sale.kind else {
+                return Err(EscrowError::InvalidSaleStatus);
+            };
+            if self.env().transferred_value() != listed_price {
+                return Err(EscrowError::WrongAmount);
+            }
+
+            let index = self.asset_index(asset_id).ok_or(EscrowError::NoActiveSale)?;
+            let seller = self.assets[index].account_owner;
+            self.assets[index].account_owner = account;
+
+            self.escrows.insert(
+                asset_id,
+                &EscrowEntry {
+                    buyer: account,
+                    seller,
+                    amount: self.env().transferred_value(),
+                    released: false,
+                },
+            );
+            self.current_sale.status = SaleStatus::Closed;
+
             self.env().emit_event(ItemBought { seller_id: account });
+            Ok(())
         }
 
+        /// Places a bid on an open English auction. Rejects bids below the
+        /// reserve or at or below the current highest bid, refunds the
+        /// previous top bidder, and moves `end_block` to
+        /// `BID_EXTENSION_WINDOW` blocks after the bid if the bid lands
+        /// within that window of closing.
+        #[ink(message, payable)]
+        pub fn place_bid(&mut self, asset_id: u32) -> Result<(), EscrowError> {
+            if self.current_sale.asset_id != asset_id
+                || self.current_sale.status != SaleStatus::AuctionOpen
+            {
+                return Err(EscrowError::InvalidSaleStatus);
+            }
+            let SaleKind::EnglishAuction {
+                reserve,
+                highest_bid,
+                highest_bidder,
+                end_block,
+            } = self.current_sale.kind
+            else {
+                return Err(EscrowError::InvalidSaleStatus);
+            };
+            let now = self.env().block_number();
+            if now >= end_block {
+                return Err(EscrowError::InvalidSaleStatus);
+            }
+
+            let bid = self.env().transferred_value();
+            if bid < reserve || bid <= highest_bid {
+                return Err(EscrowError::WrongAmount);
+            }
+
+            if let Some(previous_bidder) = highest_bidder {
+                self.env()
+                    .transfer(previous_bidder, highest_bid)
+                    .map_err(|_| EscrowError::TransferFailed)?;
+            }
+
+            let end_block = if end_block - now < BID_EXTENSION_WINDOW {
+                now + BID_EXTENSION_WINDOW
+            } else {
+                end_block
+            };
+            self.current_sale.kind = SaleKind::EnglishAuction {
+                reserve,
+                highest_bid: bid,
+                highest_bidder: Some(self.env().caller()),
+                end_block,
+            };
+            Ok(())
+        }
+
+        /// Settles a closed English auction: transfers the asset to the
+        /// highest bidder and escrows their locked bid for the seller, the
+        /// same way `buy_asset` does for fixed-price sales. The seller is
+        /// read from the contract's own `self.assets` inventory, never from
+        /// a caller-supplied `Asset`.
+        #[ink(message)]
+        pub fn settle_auction(&mut self, asset_id: u32) -> Result<(), EscrowError> {
+            if self.current_sale.asset_id != asset_id
+                || self.current_sale.status != SaleStatus::AuctionOpen
+            {
+                return Err(EscrowError::InvalidSaleStatus);
+            }
+            let SaleKind::EnglishAuction {
+                highest_bid,
+                highest_bidder,
+                end_block,
+                ..
+            } = self.current_sale.kind
+            else {
+                return Err(EscrowError::InvalidSaleStatus);
+            };
+            if self.env().block_number() < end_block {
+                return Err(EscrowError::InvalidSaleStatus);
+            }
+            let winner = highest_bidder.ok_or(EscrowError::NoActiveSale)?;
+
+            let index = self.asset_index(asset_id).ok_or(EscrowError::NoActiveSale)?;
+            let seller = self.assets[index].account_owner;
+            self.assets[index].account_owner = winner;
+
+            self.escrows.insert(
+                asset_id,
+                &EscrowEntry {
+                    buyer: winner,
+                    seller,
+                    amount: highest_bid,
+                    released: false,
+                },
+            );
+            self.current_sale.status = SaleStatus::AuctionSettled;
+            Ok(())
+        }
+
+        /// Pays the escrowed funds for `asset_id` out to the seller. Only the
+        /// buyer who funded the escrow may release it.
+        #[ink(message)]
+        pub fn release_funds(&mut self, asset_id: u32) -> Result<(), EscrowError> {
+            let mut escrow = self.escrows.get(asset_id).ok_or(EscrowError::NoActiveSale)?;
+            if self.env().caller() != escrow.buyer {
+                return Err(EscrowError::Unauthorized);
+            }
+            if escrow.released {
+                return Err(EscrowError::AlreadySettled);
+            }
+            escrow.released = true;
+            self.escrows.insert(asset_id, &escrow);
+            self.env()
+                .transfer(escrow.seller, escrow.amount)
+                .map_err(|_| EscrowError::TransferFailed)
+        }
+
+        /// Cancels a fixed-price sale that hasn't been bought yet.
+        #[ink(message)]
+        pub fn cancel_sale(&mut self, asset_id: u32) -> Result<(), EscrowError> {
+            if self.current_sale.asset_id != asset_id || self.current_sale.status != SaleStatus::Listed
+            {
+                return Err(EscrowError::InvalidSaleStatus);
+            }
+            self.current_sale.status = SaleStatus::Closed;
+            Ok(())
+        }
+
+        /// Refunds the buyer of an unreleased escrow. Only the seller, who
+        /// forgoes payment by doing so, may cancel a settled sale this way.
+        #[ink(message)]
+        pub fn refund(&mut self, asset_id: u32) -> Result<(), EscrowError> {
+            let escrow = self.escrows.get(asset_id).ok_or(EscrowError::NoActiveSale)?;
+            if self.env().caller() != escrow.seller {
+                return Err(EscrowError::Unauthorized);
+            }
+            if escrow.released {
+                return Err(EscrowError::AlreadySettled);
+            }
+            self.escrows.remove(asset_id);
+            self.env()
+                .transfer(escrow.buyer, escrow.amount)
+                .map_err(|_| EscrowError::TransferFailed)
+        }
+
+        /// Queues an encrypted review for `seller`. Reviews accumulate
+        /// on-chain until an off-chain worker aggregates them and submits the
+        /// result through `rollup_commit`.
         #[ink(message)]
         pub fn give_seller_review(&mut self, seller: AccountId, encrypted_change: Vec<u8>) {
-            //TODO: Check sellerId
-            //Update seller review
-            self.env().emit_event(ItemBought { seller_id: seller });
+            let mut queue = self.review_queue.get(seller).unwrap_or_default();
+            queue.push(encrypted_change);
+            self.review_queue.insert(seller, &queue);
+
+            let queue_version = self.queue_version.get(seller).unwrap_or(0) + 1;
+            self.queue_version.insert(seller, &queue_version);
+
+            self.env().emit_event(ReviewQueued {
+                seller,
+                queue_version,
+            });
         }
 
+        /// Hashes `reviews` the same way the `aggregation` guest hashes the
+        /// queue it was handed: each entry length-prefixed so review
+        /// boundaries can't collide, then SHA-256'd as one buffer.
+        fn hash_reviews(reviews: &[Vec<u8>]) -> Hash {
+            let mut preimage = Vec::new();
+            for review in reviews {
+                preimage.extend_from_slice(&(review.len() as u32).to_le_bytes());
+                preimage.extend_from_slice(review);
+            }
+            Hash::from(*Impl::hash_bytes(&preimage).as_bytes())
+        }
+
+        /// Commits an off-chain worker's aggregation of `seller`'s queued
+        /// reviews, adding the journal's `reputation_delta` to the seller's
+        /// existing reputation rather than replacing it, so each rollup
+        /// accumulates onto prior ones instead of discarding them.
+        /// `expected_queue_version` must match the version the worker read
+        /// the queue at, and the attestation's journal must commit the same
+        /// `(seller, expected_queue_version)` pair and a hash of exactly the
+        /// reviews currently queued, so a commit racing a fresh
+        /// `give_seller_review` — or one computed over a different queue
+        /// entirely — is rejected rather than silently accepted.
+        #[ink(message)]
+        pub fn rollup_commit(
+            &mut self,
+            seller: AccountId,
+            expected_queue_version: u32,
+            proof: AggregationProof,
+        ) -> Result<(), RollupError> {
+            if self.queue_version.get(seller).unwrap_or(0) != expected_queue_version {
+                return Err(RollupError::VersionMismatch);
+            }
+
+            let journal = AggregationJournal::decode(&proof.journal)?;
+            if journal.seller != seller || journal.queue_version != expected_queue_version {
+                return Err(RollupError::SellerMismatch);
+            }
+            let queued_reviews = self.review_queue.get(seller).unwrap_or_default();
+            if journal.reviews_hash != Self::hash_reviews(&queued_reviews) {
+                return Err(RollupError::ReviewsMismatch);
+            }
+
+            let receipt = Receipt {
+                journal: proof.journal,
+                seal: proof.seal,
+            };
+            receipt
+                .verify(AGGREGATION_ID)
+                .map_err(|_| RollupError::InvalidProof)?;
+
+            let mut new_reputation = 0;
+            if let Some(user) = self.users.iter_mut().find(|user| user.account_id == seller) {
+                user.reputation = user.reputation.saturating_add(journal.reputation_delta);
+                new_reputation = user.reputation;
+            }
+            self.review_queue.remove(seller);
+            self.queue_version.insert(seller, &(expected_queue_version + 1));
 
+            self.env().emit_event(RollupCommitted {
+                seller,
+                new_reputation,
+            });
+            Ok(())
+        }
+
+        /// Verifies a reputation proof and, if it checks out, registers
+        /// `hash` as a new leaf of the seller-commitment Merkle tree.
         #[ink(message)]
-        pub fn update_seller_reputation(&self, hash: Hash, review_proof: [u32; 8]) {
-            let review_proof = Digest::from(review_proof);
-            unimplemented!()
-            //TBD
+        pub fn update_seller_reputation(
+            &mut self,
+            hash: Hash,
+            review_proof: ReputationProof,
+            reputation_threshold: u32,
+        ) -> Result<(), ReputationError> {
+            self.verify_reputation_proof(&review_proof, reputation_threshold)?;
+            self.register_seller(hash)?;
+            Ok(())
         }
     }
 
@@ -151,20 +1018,541 @@ mod marketplace {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
 
-        /// We test if the default constructor does its job.
+        fn accounts() -> ink::env::test::DefaultAccounts<crate::MarketplaceEnv> {
+            ink::env::test::default_accounts::<crate::MarketplaceEnv>()
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<crate::MarketplaceEnv>(caller);
+        }
+
+        fn set_transferred(value: Balance) {
+            ink::env::test::set_value_transferred::<crate::MarketplaceEnv>(value);
+        }
+
+        /// Tops up the contract's own balance so `env().transfer` calls made
+        /// during the test (e.g. `release_funds`, `refund`) have funds to pay
+        /// out of.
+        fn fund_contract(amount: Balance) {
+            let contract = ink::env::test::callee::<crate::MarketplaceEnv>();
+            ink::env::test::set_account_balance::<crate::MarketplaceEnv>(contract, amount);
+        }
+
+        fn listed_asset(id: u32, account_owner: AccountId) -> Asset {
+            Asset {
+                id,
+                account_owner,
+                name: String::from("asset"),
+                description: Vec::new(),
+                purchasable: true,
+            }
+        }
+
+        fn marketplace_with_fixed_price_sale(
+            seller: AccountId,
+            asset_id: u32,
+            price: Balance,
+        ) -> Marketplace {
+            let mut marketplace = Marketplace::new(vec![listed_asset(asset_id, seller)], Vec::new());
+            marketplace.current_sale = Sale {
+                status: SaleStatus::Listed,
+                kind: SaleKind::FixedPrice { price },
+                asset_id,
+            };
+            marketplace
+        }
+
+        fn marketplace_with_auction(
+            seller: AccountId,
+            asset_id: u32,
+            highest_bid: Balance,
+            highest_bidder: Option<AccountId>,
+            end_block: BlockNumber,
+        ) -> Marketplace {
+            let mut marketplace = Marketplace::new(vec![listed_asset(asset_id, seller)], Vec::new());
+            marketplace.current_sale = Sale {
+                status: SaleStatus::AuctionOpen,
+                kind: SaleKind::EnglishAuction {
+                    reserve: 50,
+                    highest_bid,
+                    highest_bidder,
+                    end_block,
+                },
+                asset_id,
+            };
+            marketplace
+        }
+
         #[ink::test]
-        fn default_works() {
-            let marketplace = Marketplace::default();
-            assert_eq!(marketplace.get(), false);
+        fn buy_asset_locks_escrow_and_transfers_ownership() {
+            let accounts = accounts();
+            let mut marketplace = marketplace_with_fixed_price_sale(accounts.alice, 1, 100);
+
+            set_caller(accounts.bob);
+            set_transferred(100);
+            assert_eq!(marketplace.buy_asset(1), Ok(()));
+
+            assert_eq!(marketplace.current_sale.status, SaleStatus::Closed);
+            assert_eq!(marketplace.assets[0].account_owner, accounts.bob);
         }
 
-        /// We test a simple use case of our contract.
         #[ink::test]
-        fn it_works() {
-            let mut marketplace = Marketplace::new(false);
-            assert_eq!(marketplace.get(), false);
-            marketplace.flip();
-            assert_eq!(marketplace.get(), true);
+        fn buy_asset_rejects_wrong_amount() {
+            let accounts = accounts();
+            let mut marketplace = marketplace_with_fixed_price_sale(accounts.alice, 1, 100);
+
+            set_caller(accounts.bob);
+            set_transferred(50);
+            assert_eq!(marketplace.buy_asset(1), Err(EscrowError::WrongAmount));
+        }
+
+        #[ink::test]
+        fn buy_asset_rejects_when_sale_not_listed() {
+            let accounts = accounts();
+            let mut marketplace = marketplace_with_fixed_price_sale(accounts.alice, 1, 100);
+            marketplace.current_sale.status = SaleStatus::Closed;
+
+            set_caller(accounts.bob);
+            set_transferred(100);
+            assert_eq!(marketplace.buy_asset(1), Err(EscrowError::InvalidSaleStatus));
+        }
+
+        #[ink::test]
+        fn release_funds_pays_seller_and_rejects_double_release() {
+            let accounts = accounts();
+            let mut marketplace = marketplace_with_fixed_price_sale(accounts.alice, 1, 100);
+            fund_contract(1_000);
+
+            set_caller(accounts.bob);
+            set_transferred(100);
+            assert_eq!(marketplace.buy_asset(1), Ok(()));
+
+            set_transferred(0);
+            assert_eq!(marketplace.release_funds(1), Ok(()));
+            assert_eq!(
+                marketplace.release_funds(1),
+                Err(EscrowError::AlreadySettled)
+            );
+        }
+
+        #[ink::test]
+        fn release_funds_rejects_non_buyer() {
+            let accounts = accounts();
+            let mut marketplace = marketplace_with_fixed_price_sale(accounts.alice, 1, 100);
+            fund_contract(1_000);
+
+            set_caller(accounts.bob);
+            set_transferred(100);
+            assert_eq!(marketplace.buy_asset(1), Ok(()));
+
+            set_caller(accounts.charlie);
+            set_transferred(0);
+            assert_eq!(
+                marketplace.release_funds(1),
+                Err(EscrowError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn refund_returns_buyer_funds_and_rejects_non_seller() {
+            let accounts = accounts();
+            let mut marketplace = marketplace_with_fixed_price_sale(accounts.alice, 1, 100);
+            fund_contract(1_000);
+
+            set_caller(accounts.bob);
+            set_transferred(100);
+            assert_eq!(marketplace.buy_asset(1), Ok(()));
+
+            set_caller(accounts.bob);
+            set_transferred(0);
+            assert_eq!(marketplace.refund(1), Err(EscrowError::Unauthorized));
+
+            set_caller(accounts.alice);
+            assert_eq!(marketplace.refund(1), Ok(()));
+            assert_eq!(marketplace.refund(1), Err(EscrowError::NoActiveSale));
+        }
+
+        #[ink::test]
+        fn place_bid_tracks_highest_bidder_and_extends_near_end() {
+            let accounts = accounts();
+            let mut marketplace =
+                marketplace_with_auction(accounts.alice, 1, 0, None, 10);
+
+            ink::env::test::set_block_number::<crate::MarketplaceEnv>(5);
+            set_caller(accounts.bob);
+            set_transferred(60);
+            assert_eq!(marketplace.place_bid(1), Ok(()));
+
+            let SaleKind::EnglishAuction {
+                highest_bid,
+                highest_bidder,
+                end_block,
+                ..
+            } = marketplace.current_sale.kind
+            else {
+                panic!("expected an english auction");
+            };
+            assert_eq!(highest_bid, 60);
+            assert_eq!(highest_bidder, Some(accounts.bob));
+            assert_eq!(end_block, 5 + BID_EXTENSION_WINDOW);
+        }
+
+        #[ink::test]
+        fn place_bid_rejects_bid_at_or_below_highest() {
+            let accounts = accounts();
+            let mut marketplace =
+                marketplace_with_auction(accounts.alice, 1, 60, Some(accounts.bob), 100);
+
+            set_caller(accounts.charlie);
+            set_transferred(60);
+            assert_eq!(marketplace.place_bid(1), Err(EscrowError::WrongAmount));
+        }
+
+        #[ink::test]
+        fn settle_auction_transfers_to_winner() {
+            let accounts = accounts();
+            let mut marketplace =
+                marketplace_with_auction(accounts.alice, 1, 80, Some(accounts.bob), 5);
+
+            ink::env::test::set_block_number::<crate::MarketplaceEnv>(10);
+            assert_eq!(marketplace.settle_auction(1), Ok(()));
+            assert_eq!(marketplace.current_sale.status, SaleStatus::AuctionSettled);
+            assert_eq!(marketplace.assets[0].account_owner, accounts.bob);
+        }
+
+        #[ink::test]
+        fn settle_auction_rejects_before_end_block() {
+            let accounts = accounts();
+            let mut marketplace =
+                marketplace_with_auction(accounts.alice, 1, 80, Some(accounts.bob), 100);
+
+            ink::env::test::set_block_number::<crate::MarketplaceEnv>(10);
+            assert_eq!(
+                marketplace.settle_auction(1),
+                Err(EscrowError::InvalidSaleStatus)
+            );
+        }
+
+        /// Encodes a fake `reputation` guest journal in the layout
+        /// `ReputationJournal::decode` expects, for exercising
+        /// `verify_reputation_proof`'s rejection paths without a real prover.
+        fn reputation_journal_bytes(root: Hash, nullifier_hash: Hash, reputation_threshold: u32) -> Vec<u8> {
+            let mut bytes = Vec::with_capacity(68);
+            bytes.extend_from_slice(root.as_ref());
+            bytes.extend_from_slice(nullifier_hash.as_ref());
+            bytes.extend_from_slice(&reputation_threshold.to_le_bytes());
+            bytes
+        }
+
+        #[ink::test]
+        fn verify_reputation_proof_rejects_malformed_journal() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            let proof = ReputationProof {
+                journal: vec![0u8; 10],
+                seal: Vec::new(),
+            };
+            assert_eq!(
+                marketplace.verify_reputation_proof(&proof, 0),
+                Err(ReputationError::MalformedJournal)
+            );
+        }
+
+        #[ink::test]
+        fn verify_reputation_proof_rejects_unknown_root() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            let bogus_root = Hash::from([0xffu8; 32]);
+            let journal = reputation_journal_bytes(bogus_root, Hash::from([1u8; 32]), 0);
+            let proof = ReputationProof { journal, seal: Vec::new() };
+            assert_eq!(
+                marketplace.verify_reputation_proof(&proof, 0),
+                Err(ReputationError::UnknownCommitmentRoot)
+            );
+        }
+
+        #[ink::test]
+        fn verify_reputation_proof_rejects_threshold_not_met() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            let root = marketplace.commitment_root();
+            let journal = reputation_journal_bytes(root, Hash::from([1u8; 32]), 5);
+            let proof = ReputationProof { journal, seal: Vec::new() };
+            assert_eq!(
+                marketplace.verify_reputation_proof(&proof, 10),
+                Err(ReputationError::ThresholdNotMet)
+            );
+        }
+
+        #[ink::test]
+        fn verify_reputation_proof_rejects_spent_nullifier() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            let root = marketplace.commitment_root();
+            let nullifier_hash = Hash::from([2u8; 32]);
+            marketplace.spent_nullifier.insert(nullifier_hash, &true);
+            let journal = reputation_journal_bytes(root, nullifier_hash, 0);
+            let proof = ReputationProof { journal, seal: Vec::new() };
+            assert_eq!(
+                marketplace.verify_reputation_proof(&proof, 0),
+                Err(ReputationError::NullifierSpent)
+            );
+        }
+
+        #[ink::test]
+        fn verify_reputation_proof_rejects_bad_seal() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            let root = marketplace.commitment_root();
+            let journal = reputation_journal_bytes(root, Hash::from([3u8; 32]), 0);
+            let proof = ReputationProof {
+                journal,
+                seal: vec![0u8; 4],
+            };
+            assert_eq!(
+                marketplace.verify_reputation_proof(&proof, 0),
+                Err(ReputationError::InvalidProof)
+            );
+        }
+
+        #[ink::test]
+        fn register_seller_updates_commitment_root() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            let initial_root = marketplace.commitment_root();
+
+            let root_after_first = marketplace
+                .register_seller(Hash::from([1u8; 32]))
+                .expect("tree has room");
+            assert_ne!(root_after_first, initial_root);
+            assert_eq!(marketplace.commitment_root(), root_after_first);
+
+            let root_after_second = marketplace
+                .register_seller(Hash::from([2u8; 32]))
+                .expect("tree has room");
+            assert_ne!(root_after_second, root_after_first);
+            assert_eq!(marketplace.commitment_root(), root_after_second);
+        }
+
+        #[ink::test]
+        fn register_seller_rejects_when_tree_full() {
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            marketplace.next_index = 1u32.checked_shl(MERKLE_DEPTH).unwrap();
+
+            assert_eq!(
+                marketplace.register_seller(Hash::from([1u8; 32])),
+                Err(ReputationError::MerkleTreeFull)
+            );
+        }
+
+        /// Encodes a fake `aggregation` guest journal in the layout
+        /// `AggregationJournal::decode` expects, for exercising
+        /// `rollup_commit`'s rejection paths without a real prover.
+        fn aggregation_journal_bytes(
+            seller: AccountId,
+            reputation_delta: u32,
+            queue_version: u32,
+            reviews_hash: Hash,
+        ) -> Vec<u8> {
+            let mut bytes = Vec::with_capacity(72);
+            bytes.extend_from_slice(seller.as_ref());
+            bytes.extend_from_slice(&reputation_delta.to_le_bytes());
+            bytes.extend_from_slice(&queue_version.to_le_bytes());
+            bytes.extend_from_slice(reviews_hash.as_ref());
+            bytes
+        }
+
+        #[ink::test]
+        fn rollup_commit_rejects_version_mismatch() {
+            let accounts = accounts();
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+
+            let journal = aggregation_journal_bytes(
+                accounts.alice,
+                10,
+                0,
+                Marketplace::hash_reviews(&Vec::new()),
+            );
+            let proof = AggregationProof { journal, seal: Vec::new() };
+            assert_eq!(
+                marketplace.rollup_commit(accounts.alice, 1, proof),
+                Err(RollupError::VersionMismatch)
+            );
+        }
+
+        #[ink::test]
+        fn rollup_commit_rejects_seller_mismatch() {
+            let accounts = accounts();
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+
+            let journal = aggregation_journal_bytes(
+                accounts.bob,
+                10,
+                0,
+                Marketplace::hash_reviews(&Vec::new()),
+            );
+            let proof = AggregationProof { journal, seal: Vec::new() };
+            assert_eq!(
+                marketplace.rollup_commit(accounts.alice, 0, proof),
+                Err(RollupError::SellerMismatch)
+            );
+        }
+
+        #[ink::test]
+        fn rollup_commit_rejects_reviews_mismatch() {
+            let accounts = accounts();
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            marketplace
+                .review_queue
+                .insert(accounts.alice, &vec![vec![1, 2, 3]]);
+
+            let journal = aggregation_journal_bytes(
+                accounts.alice,
+                10,
+                0,
+                Marketplace::hash_reviews(&Vec::new()),
+            );
+            let proof = AggregationProof { journal, seal: Vec::new() };
+            assert_eq!(
+                marketplace.rollup_commit(accounts.alice, 0, proof),
+                Err(RollupError::ReviewsMismatch)
+            );
+        }
+
+        #[ink::test]
+        fn rollup_commit_rejects_bad_seal() {
+            let accounts = accounts();
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+
+            let journal = aggregation_journal_bytes(
+                accounts.alice,
+                10,
+                0,
+                Marketplace::hash_reviews(&Vec::new()),
+            );
+            let proof = AggregationProof {
+                journal,
+                seal: vec![0u8; 4],
+            };
+            assert_eq!(
+                marketplace.rollup_commit(accounts.alice, 0, proof),
+                Err(RollupError::InvalidProof)
+            );
+        }
+
+        #[ink::test]
+        fn set_role_requires_owner() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                marketplace.set_role(accounts.charlie, Some(Role::Moderator)),
+                Err(AccessError::NotOwner)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                marketplace.set_role(accounts.charlie, Some(Role::Moderator)),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn slash_reputation_requires_at_least_moderator_role_and_saturates() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            marketplace.users.push(UserProfile {
+                account_id: accounts.bob,
+                reputation: 5,
+            });
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                marketplace.slash_reputation(accounts.bob, 3),
+                Err(AccessError::Unauthorized)
+            );
+
+            // Granting `Admin`, not `Moderator`, checks that `Admin` also
+            // satisfies a `Moderator`-minimum gate.
+            set_caller(accounts.alice);
+            marketplace
+                .set_role(accounts.charlie, Some(Role::Admin))
+                .unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(marketplace.slash_reputation(accounts.bob, 100), Ok(()));
+            assert_eq!(marketplace.users[0].reputation, 0);
+        }
+
+        #[ink::test]
+        fn flush_review_queue_requires_admin_role() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+            marketplace.review_queue.insert(accounts.bob, &vec![vec![1]]);
+            marketplace.queue_version.insert(accounts.bob, &3);
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                marketplace.flush_review_queue(accounts.bob),
+                Err(AccessError::Unauthorized)
+            );
+
+            set_caller(accounts.alice);
+            marketplace
+                .set_role(accounts.charlie, Some(Role::Moderator))
+                .unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                marketplace.flush_review_queue(accounts.bob),
+                Err(AccessError::Unauthorized)
+            );
+
+            set_caller(accounts.alice);
+            marketplace
+                .set_role(accounts.charlie, Some(Role::Admin))
+                .unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(marketplace.flush_review_queue(accounts.bob), Ok(()));
+            assert_eq!(marketplace.review_queue.get(accounts.bob), None);
+            assert_eq!(marketplace.queue_version.get(accounts.bob), Some(4));
+        }
+
+        #[ink::test]
+        fn transfer_ownership_requires_owner_and_updates_owner() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                marketplace.transfer_ownership(accounts.bob),
+                Err(AccessError::NotOwner)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(marketplace.transfer_ownership(accounts.bob), Ok(()));
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                marketplace.transfer_ownership(accounts.charlie),
+                Err(AccessError::NotOwner)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(marketplace.transfer_ownership(accounts.charlie), Ok(()));
+        }
+
+        #[ink::test]
+        fn upgrade_rejects_non_owner() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut marketplace = Marketplace::new(Vec::new(), Vec::new());
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                marketplace.upgrade(Hash::from([1u8; 32])),
+                Err(AccessError::NotOwner)
+            );
         }
     }
 
@@ -1,7 +1,33 @@
-// TODO: Update the name of the method loaded by the prover. E.g., if the method is `multiply`, replace `METHOD_NAME_ID` with `MULTIPLY_ID` and replace `METHOD_NAME_PATH` with `MULTIPLY_PATH`
 use methods::{REPUTATION_ID, REPUTATION_PATH};
+use risc0_zkvm::serde::to_vec;
 use risc0_zkvm::Prover;
-// use risc0_zkvm::serde::{from_slice, to_vec};
+use scale::Encode;
+use serde::Serialize;
+
+/// Depth of the seller-commitment Merkle tree; must match
+/// `marketplace::MERKLE_DEPTH` and the guest's own `MERKLE_DEPTH`.
+const MERKLE_DEPTH: usize = 20;
+
+/// Mirrors the guest's private witness. In a real deployment `secret`,
+/// `score` and `siblings` would be read back from the seller's own wallet
+/// state and the tree's `commitment_root` event log rather than hard-coded.
+#[derive(Serialize)]
+struct ReputationInput {
+    secret: [u8; 32],
+    score: u32,
+    siblings: [[u8; 32]; MERKLE_DEPTH],
+    leaf_index: u32,
+    reputation_threshold: u32,
+}
+
+/// A receipt's journal and seal, SCALE-encoded in the shape the
+/// `put_asset_on_sale` and `update_seller_reputation` contract messages
+/// expect as their `zk_proof`/`review_proof` argument.
+#[derive(Encode)]
+struct ReputationProof {
+    journal: Vec<u8>,
+    seal: Vec<u8>,
+}
 
 fn main() {
     // Make the prover.
@@ -11,24 +37,40 @@ fn main() {
         "Prover should be constructed from valid method source code and corresponding method ID",
     );
 
-    // TODO: Implement communication with the guest here
+    // Send the seller's witness to the guest.
+    let input = ReputationInput {
+        secret: [0u8; 32],
+        score: 0,
+        siblings: [[0u8; 32]; MERKLE_DEPTH],
+        leaf_index: 0,
+        reputation_threshold: 0,
+    };
+    prover.add_input_u32_slice(&to_vec(&input).expect("input should be serializable"));
 
     // Run prover & generate receipt
     let receipt = prover.run()
         .expect("Code should be provable unless it 1) had an error or 2) overflowed the cycle limit. See `embed_methods_with_options` for information on adjusting maximum cycle count.");
 
-    // Optional: Verify receipt to confirm that recipients will also be able to verify your receipt
+    // Verify receipt to confirm that recipients will also be able to verify your receipt
     receipt.verify(REPUTATION_ID).expect(
         "Code you have proven should successfully verify; did you specify the correct method ID?",
     );
 
-    // TODO: Implement code for transmitting or serializing the receipt for other parties to verify here
+    // Hand the journal + seal to the marketplace contract so
+    // `put_asset_on_sale`/`update_seller_reputation` can re-verify the seal
+    // against `REPUTATION_ID` and check the committed nullifier on-chain.
+    let proof = ReputationProof {
+        journal: receipt.journal.clone(),
+        seal: receipt.seal.clone(),
+    };
+    submit_reputation_proof(&proof);
 }
 
-
-// let mut prover =
-// Prover::new(MULTIPLY_ELF).expect("Prover should be constructed from valid ELF binary");
-
-// // Next we send a & b to the guest
-// prover.add_input_u32_slice(&to_vec(&a).expect("should be serializable"));
-// prover.add_input_u32_slice(&to_vec(&b).expect("should be serializable"));
+/// Encodes the proof for submission as a contract call argument. Wiring this
+/// into an actual extrinsic requires a Substrate RPC client, which this host
+/// does not depend on yet; until then the encoded payload is written to disk
+/// for the caller to submit by hand.
+fn submit_reputation_proof(proof: &ReputationProof) {
+    std::fs::write("reputation_proof.scale", proof.encode())
+        .expect("should be able to write the proof payload");
+}
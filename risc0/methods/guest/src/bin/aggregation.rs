@@ -0,0 +1,81 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+risc0_zkvm::guest::entry!(main);
+
+use alloc::vec::Vec;
+use risc0_zkvm::guest::env;
+use risc0_zkvm::sha::{Impl, Sha256};
+use serde::{Deserialize, Serialize};
+
+/// Private witness handed to the guest by the off-chain worker: the seller
+/// whose reviews are being aggregated, the exact queue of encrypted reviews
+/// it read on-chain, the worker's decryption key, and the queue's version at
+/// read time.
+#[derive(Deserialize, Serialize)]
+struct AggregationInput {
+    seller: [u8; 32],
+    encrypted_reviews: Vec<Vec<u8>>,
+    decryption_key: [u8; 32],
+    queue_version: u32,
+}
+
+/// XORs `ciphertext` against `key`, repeating `key` as needed. Reviews are
+/// queued pre-encrypted with this stream cipher so only holders of
+/// `decryption_key` can read their contents off-chain.
+fn decrypt(ciphertext: &[u8], key: &[u8; 32]) -> Vec<u8> {
+    ciphertext
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| byte ^ key[i % key.len()])
+        .collect()
+}
+
+/// Decodes a decrypted review's leading 4 bytes as its little-endian
+/// reputation contribution. A review shorter than 4 bytes is zero-padded
+/// rather than panicking the guest.
+fn review_score(plaintext: &[u8]) -> u32 {
+    let mut bytes = [0u8; 4];
+    let len = plaintext.len().min(4);
+    bytes[..len].copy_from_slice(&plaintext[..len]);
+    u32::from_le_bytes(bytes)
+}
+
+/// Hashes the exact queued ciphertexts (length-prefixed so review boundaries
+/// can't collide), so the journal commits to precisely the queue this guest
+/// aggregated, not just its length or version.
+fn hash_reviews(encrypted_reviews: &[Vec<u8>]) -> [u8; 32] {
+    let mut preimage = Vec::new();
+    for review in encrypted_reviews {
+        preimage.extend_from_slice(&(review.len() as u32).to_le_bytes());
+        preimage.extend_from_slice(review);
+    }
+    *Impl::hash_bytes(&preimage).as_bytes()
+}
+
+/// Decrypts and aggregates `input.encrypted_reviews` into this batch's
+/// reputation contribution — the guest only ever sees the queued reviews, not
+/// the seller's running score, so `rollup_commit` adds `reputation_delta` to
+/// the seller's existing reputation rather than treating it as an absolute
+/// value. Commits `(seller, reputation_delta, queue_version, reviews_hash)`
+/// so `rollup_commit` can check both that the queue hasn't moved on since the
+/// worker read it (`queue_version`) and that this attestation was computed
+/// over exactly the queued ciphertexts it's settling (`reviews_hash`), not a
+/// queue that changed underneath it.
+pub fn main() {
+    let input: AggregationInput = env::read();
+
+    let reputation_delta = input
+        .encrypted_reviews
+        .iter()
+        .map(|review| review_score(&decrypt(review, &input.decryption_key)))
+        .fold(0u32, |total, score| total.saturating_add(score));
+    let reviews_hash = hash_reviews(&input.encrypted_reviews);
+
+    env::commit(&input.seller);
+    env::commit(&reputation_delta);
+    env::commit(&input.queue_version);
+    env::commit(&reviews_hash);
+}
@@ -0,0 +1,102 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+risc0_zkvm::guest::entry!(main);
+
+use alloc::vec::Vec;
+use risc0_zkvm::guest::env;
+use risc0_zkvm::sha::{Impl, Sha256};
+use serde::{Deserialize, Serialize};
+
+/// Depth of the seller-commitment Merkle tree. Must match
+/// `marketplace::MERKLE_DEPTH` or a correctly-witnessed proof will recompute
+/// the wrong root.
+const MERKLE_DEPTH: usize = 20;
+
+/// Private witness handed to the guest by the seller's wallet: the secret
+/// commitment preimage and real reputation score behind the leaf registered
+/// via `register_seller`, the sibling path proving that leaf's membership,
+/// and the leaf's index in the tree.
+#[derive(Deserialize, Serialize)]
+struct ReputationInput {
+    /// Secret nullifier preimage. `nullifier_hash` is derived from this, so a
+    /// caller can't supply an arbitrary `nullifier_hash` in the journal.
+    secret: [u8; 32],
+    /// The seller's real reputation score, never revealed in the journal —
+    /// only whether it meets `reputation_threshold`.
+    score: u32,
+    /// Sibling hash at each level of the path from the leaf to the root,
+    /// ordered leaf-to-root, matching `Marketplace::hash_pair`'s convention.
+    siblings: [[u8; 32]; MERKLE_DEPTH],
+    /// The leaf's index in the tree; its bits select, at each level, whether
+    /// the current node is the left or right child of its parent.
+    leaf_index: u32,
+    /// Public claim: the score meets or exceeds this.
+    reputation_threshold: u32,
+}
+
+/// Combines two sibling nodes with the same SHA-256 `Marketplace::hash_pair`
+/// uses on-chain, so the root this guest recomputes agrees with the
+/// contract's `commitment_root`.
+fn hash_pair(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(64);
+    preimage.extend_from_slice(&left);
+    preimage.extend_from_slice(&right);
+    *Impl::hash_bytes(&preimage).as_bytes()
+}
+
+/// Recomputes the leaf's commitment as `hash(secret || score)`, then walks
+/// `siblings` up to the root, so a prover can only produce a matching root by
+/// knowing a `(secret, score)` pair actually registered at `leaf_index`.
+fn merkle_root(
+    secret: [u8; 32],
+    score: u32,
+    siblings: [[u8; 32]; MERKLE_DEPTH],
+    leaf_index: u32,
+) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(36);
+    preimage.extend_from_slice(&secret);
+    preimage.extend_from_slice(&score.to_le_bytes());
+    let mut current = *Impl::hash_bytes(&preimage).as_bytes();
+
+    let mut index = leaf_index;
+    for sibling in siblings {
+        current = if index % 2 == 0 {
+            hash_pair(current, sibling)
+        } else {
+            hash_pair(sibling, current)
+        };
+        index /= 2;
+    }
+    current
+}
+
+/// Verifies that `input.secret`/`input.score` are a real leaf registered in
+/// the tree rooted at the recomputed `commitment_root`, and that `score`
+/// meets `reputation_threshold`, before committing
+/// `(commitment_root, nullifier_hash, reputation_threshold)` as the journal.
+/// The contract checks the threshold against the sale requirement, rejects
+/// already-spent `nullifier_hash`es, checks `commitment_root` against its own
+/// recent roots, and verifies this receipt's seal before trusting any of it.
+pub fn main() {
+    let input: ReputationInput = env::read();
+
+    assert!(
+        input.score >= input.reputation_threshold,
+        "score does not meet the claimed threshold"
+    );
+
+    let commitment_root = merkle_root(
+        input.secret,
+        input.score,
+        input.siblings,
+        input.leaf_index,
+    );
+    let nullifier_hash = *Impl::hash_bytes(&input.secret).as_bytes();
+
+    env::commit(&commitment_root);
+    env::commit(&nullifier_hash);
+    env::commit(&input.reputation_threshold);
+}